@@ -1,4 +1,5 @@
 use std::collections::HashMap;
+use std::fmt;
 use std::io::Read;
 
 /// Bencode is a simple encoding format used by BitTorrent clients.
@@ -36,15 +37,113 @@ pub enum Bencode {
     Int(i64, Pos),
     String(Vec<u8>, Pos),
     List(Vec<Bencode>, Pos),
-    Dict(HashMap<String, Bencode>, Pos),
+    /// Dict keys are raw byte strings, not `String`: bencode keys are only
+    /// ASCII field names by convention, but BitTorrent v2's `piece layers`
+    /// dict is keyed by raw 32-byte pieces-root hashes, and decoding those
+    /// lossily to UTF-8 can collide two distinct keys onto one entry.
+    Dict(HashMap<Vec<u8>, Bencode>, Pos),
+}
+
+impl Bencode {
+    /// The byte range in the original input this value was parsed from.
+    pub fn pos(&self) -> &Pos {
+        match self {
+            Bencode::Int(_, pos) => pos,
+            Bencode::String(_, pos) => pos,
+            Bencode::List(_, pos) => pos,
+            Bencode::Dict(_, pos) => pos,
+        }
+    }
+
+    /// Encode this value back to canonical bencode bytes.
+    /// Dictionary keys are emitted sorted lexicographically by their raw
+    /// bytes, per the BitTorrent spec, so re-encoding a parsed `.torrent`
+    /// reproduces the exact original bytes.
+    #[allow(dead_code)]
+    pub fn encode(&self) -> Vec<u8> {
+        let mut out = Vec::new();
+        Encoder::encode_into(self, &mut out);
+        out
+    }
+}
+
+#[allow(dead_code)]
+struct Encoder;
+
+impl Encoder {
+    #[allow(dead_code)]
+    fn encode_into(value: &Bencode, out: &mut Vec<u8>) {
+        match value {
+            Bencode::Int(i, _) => {
+                out.push(b'i');
+                out.extend_from_slice(i.to_string().as_bytes());
+                out.push(b'e');
+            },
+            Bencode::String(s, _) => {
+                out.extend_from_slice(s.len().to_string().as_bytes());
+                out.push(b':');
+                out.extend_from_slice(s);
+            },
+            Bencode::List(items, _) => {
+                out.push(b'l');
+                for item in items {
+                    Encoder::encode_into(item, out);
+                }
+                out.push(b'e');
+            },
+            Bencode::Dict(dict, _) => {
+                out.push(b'd');
+                let mut keys: Vec<&Vec<u8>> = dict.keys().collect();
+                keys.sort();
+                for key in keys {
+                    out.extend_from_slice(key.len().to_string().as_bytes());
+                    out.push(b':');
+                    out.extend_from_slice(key);
+                    Encoder::encode_into(&dict[key], out);
+                }
+                out.push(b'e');
+            },
+        }
+    }
 }
 
 #[derive(Debug, PartialEq)]
 pub struct Pos {
-    start: usize,
-    end: usize
+    pub(crate) start: usize,
+    pub(crate) end: usize
+}
+
+/// A bencode decoding failure, carrying the byte offset in the input where
+/// the tokenizer noticed the problem.
+#[derive(Debug, PartialEq, Clone)]
+pub enum BencodeError {
+    UnexpectedEof(usize),
+    InvalidToken(usize),
+    InvalidInteger(usize),
+    ExpectedColon(usize),
+    InvalidStringLength(usize),
+    StringOutOfBounds(usize),
+    AllocationTooLarge(usize),
+    TrailingData(usize),
 }
 
+impl fmt::Display for BencodeError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            BencodeError::UnexpectedEof(i) => write!(f, "unexpected end of input at byte {}", i),
+            BencodeError::InvalidToken(i) => write!(f, "invalid token at byte {}", i),
+            BencodeError::InvalidInteger(i) => write!(f, "invalid integer at byte {}", i),
+            BencodeError::ExpectedColon(i) => write!(f, "expected ':' at byte {}", i),
+            BencodeError::InvalidStringLength(i) => write!(f, "invalid string length at byte {}", i),
+            BencodeError::StringOutOfBounds(i) => write!(f, "string length runs past end of input at byte {}", i),
+            BencodeError::AllocationTooLarge(i) => write!(f, "string length at byte {} exceeds the allocation limit", i),
+            BencodeError::TrailingData(i) => write!(f, "trailing data after top-level value at byte {}", i),
+        }
+    }
+}
+
+impl std::error::Error for BencodeError {}
+
 enum Token {
     Int(i64),
     String(Vec<u8>),
@@ -53,23 +152,78 @@ enum Token {
     ListDictEnd,
 }
 
-struct Tokenizer {
-    data: Vec<u8>,
+/// Default cap on how large a single bencode string token may declare
+/// itself to be. Chosen well above any real torrent field (a `pieces`
+/// string for a multi-gigabyte, many-file torrent is still only megabytes)
+/// while still refusing a malformed `<huge-len>:` before any of its bytes
+/// arrive.
+const DEFAULT_MAX_ALLOC: usize = 256 * 1024 * 1024;
+
+/// How many bytes to pull from the reader per underlying `read` call.
+const FILL_CHUNK: usize = 64 * 1024;
+
+/// Max digits a bencode string length prefix may have before we give up on
+/// it. `u64::MAX` is 20 digits, so anything longer is already malformed and
+/// not worth buffering further input to confirm.
+const MAX_LENGTH_DIGITS: usize = 20;
+
+/// Max characters a bencode integer token's body (digits plus an optional
+/// leading `-`) may have before we give up on it. `i64::MIN` is 20
+/// characters including its sign, so anything longer is already malformed.
+const MAX_INT_DIGITS: usize = 20;
+
+/// Pulls bytes incrementally from a `Read` rather than buffering the whole
+/// input up front, so multi-gigabyte `.torrent` files don't need to be
+/// fully materialized before parsing can start. Bytes are still retained
+/// once read (callers need the raw input to hash an info-dict `Pos` span),
+/// but they're fetched lazily and indexed by an absolute offset.
+struct Tokenizer<'a> {
+    reader: &'a mut dyn Read,
+    buffer: Vec<u8>,
     index: usize,
+    eof: bool,
+    max_alloc: usize,
 }
 
-impl Tokenizer {
-    fn new<'a>(data: Vec<u8>) -> Tokenizer {
-        Tokenizer{
-            data,
+impl<'a> Tokenizer<'a> {
+    fn new(reader: &'a mut dyn Read, max_alloc: usize) -> Tokenizer<'a> {
+        Tokenizer {
+            reader,
+            buffer: Vec::new(),
             index: 0,
+            eof: false,
+            max_alloc,
         }
     }
-    fn next(&mut self) -> Result<Token, String> {
-        if self.index >= self.data.len() {
-            return Err("No more tokens".to_string());
+
+    /// Pull bytes from the reader until at least `upto` bytes are buffered
+    /// or the stream is exhausted.
+    fn fill(&mut self, upto: usize) {
+        while !self.eof && self.buffer.len() < upto {
+            let mut chunk = [0u8; FILL_CHUNK];
+            match self.reader.read(&mut chunk) {
+                Ok(0) | Err(_) => self.eof = true,
+                Ok(n) => self.buffer.extend_from_slice(&chunk[..n]),
+            }
+        }
+    }
+
+    fn byte_at(&mut self, i: usize) -> Option<u8> {
+        self.fill(i + 1);
+        self.buffer.get(i).copied()
+    }
+
+    fn slice(&mut self, start: usize, end: usize) -> Option<&[u8]> {
+        self.fill(end);
+        if self.buffer.len() >= end {
+            Some(&self.buffer[start..end])
+        } else {
+            None
         }
-        let c = self.data[self.index] as char;
+    }
+
+    fn next(&mut self) -> Result<Token, BencodeError> {
+        let c = self.byte_at(self.index).ok_or(BencodeError::UnexpectedEof(self.index))? as char;
         match c {
             // Int := "i" IntValue "e"
             'i' => self.next_int(),
@@ -86,38 +240,71 @@ impl Tokenizer {
                 self.index += 1;
                 Ok(Token::ListStart)
             },
-            _ => Err("Invalid token".to_string()),
+            _ => Err(BencodeError::InvalidToken(self.index)),
         }
     }
-    fn next_string(&mut self) -> Result<Token, String> {
+    fn next_string(&mut self) -> Result<Token, BencodeError> {
         let start = self.index;
         loop {
-            let c = self.data[self.index];
+            // Bound the digit scan itself, not just the parsed length: an
+            // unterminated run of digits (no ':' ever arrives) would
+            // otherwise force `byte_at` to keep filling the buffer from the
+            // reader forever before `max_alloc` gets a chance to check
+            // anything. No well-formed length needs more digits than a
+            // u64 can hold.
+            if self.index - start > MAX_LENGTH_DIGITS {
+                return Err(BencodeError::InvalidStringLength(start));
+            }
+            let c = self.byte_at(self.index).ok_or(BencodeError::UnexpectedEof(self.index))?;
             match c as char {
                 '0'..='9' => self.index += 1,
                 ':' => break,
-                _ => return Err("Invalid token in string".to_string()),
+                _ => return Err(BencodeError::ExpectedColon(self.index)),
             }
         }
+        // Reject leading zeros (e.g. "04:spam") instead of silently
+        // accepting a length no well-formed encoder would emit.
+        if self.index - start > 1 && self.buffer[start] == b'0' {
+            return Err(BencodeError::InvalidStringLength(start));
+        }
 
-        let length: usize = std::str::from_utf8(&self.data[start..self.index])
-            .map_err(|e| e.to_string())?
+        let length: usize = std::str::from_utf8(&self.buffer[start..self.index])
+            .map_err(|_| BencodeError::InvalidStringLength(start))?
             .parse::<usize>()
-            .map_err(|e| e.to_string())?;
-        let string = self.data[self.index+1..self.index+length+1].to_vec();
-        self.index += length + 1;
+            .map_err(|_| BencodeError::InvalidStringLength(start))?;
+        // Reject an oversized length before pulling a single byte of the
+        // string itself, so a malformed `<huge-len>:` can't force a large
+        // allocation ahead of the data actually arriving.
+        if length > self.max_alloc {
+            return Err(BencodeError::AllocationTooLarge(start));
+        }
+        let string_start = self.index + 1;
+        let string_end = string_start + length;
+        let string = match self.slice(string_start, string_end) {
+            Some(s) => s.to_vec(),
+            None => return Err(BencodeError::StringOutOfBounds(start)),
+        };
+        self.index = string_end;
         Ok(Token::String(string))
     }
-    fn next_int(&mut self) -> Result<Token, String> {
+    fn next_int(&mut self) -> Result<Token, BencodeError> {
+        let marker = self.index;
         self.index += 1; // skip 'i'
         let start = self.index;
         loop {
-            let c = self.data[self.index] as char;
+            // Bound the digit scan itself, same as `next_string`'s
+            // `MAX_LENGTH_DIGITS` guard: an unterminated run of bytes with
+            // no `e` would otherwise force `byte_at` to keep filling the
+            // buffer from the reader forever before any check fires.
+            if self.index - start > MAX_INT_DIGITS {
+                return Err(BencodeError::InvalidInteger(marker));
+            }
+            let c = self.byte_at(self.index).ok_or(BencodeError::UnexpectedEof(self.index))? as char;
             if c == 'e' {
-                let value = String::from_utf8(self.data[start..self.index].to_vec())
-                    .map_err(|e| e.to_string())?
+                let value = std::str::from_utf8(&self.buffer[start..self.index])
+                    .map_err(|_| BencodeError::InvalidInteger(marker))?
                     .parse::<i64>()
-                    .map_err(|e| e.to_string())?;
+                    .map_err(|_| BencodeError::InvalidInteger(marker))?;
                 self.index += 1; // skip 'e'
                 return Ok(Token::Int(value));
             }
@@ -126,84 +313,91 @@ impl Tokenizer {
     }
 }
 
-pub struct Parser {
-    tokenizer: Tokenizer,
+pub struct Parser<'a> {
+    tokenizer: Tokenizer<'a>,
 }
 
-impl Parser {
-    pub fn new<T: Read>(reader: &mut T) -> Parser {
-        let mut buffer = Vec::new();
-        reader.read_to_end(&mut buffer).unwrap();
+impl<'a> Parser<'a> {
+    pub fn new(reader: &'a mut dyn Read) -> Parser<'a> {
+        Parser{
+            tokenizer: Tokenizer::new(reader, DEFAULT_MAX_ALLOC),
+        }
+    }
+
+    /// Like `new`, but with a caller-chosen cap on the allocation a single
+    /// bencode string token may trigger, instead of the default.
+    #[allow(dead_code)]
+    pub fn with_max_alloc(reader: &'a mut dyn Read, max_alloc: usize) -> Parser<'a> {
         Parser{
-            tokenizer: Tokenizer::new(buffer),
+            tokenizer: Tokenizer::new(reader, max_alloc),
+        }
+    }
+
+    /// The bytes read from the underlying reader so far. Useful for hashing
+    /// a sub-range identified by a value's `Pos` (e.g. an info-hash) without
+    /// re-encoding, so non-canonical inputs still hash correctly. After a
+    /// full `parse()`, this is the entire input.
+    pub fn raw(&self) -> &[u8] {
+        &self.tokenizer.buffer
+    }
+
+    /// Parse a single top-level bencode value and return a Bencode enum.
+    /// This implementation is using a recursive descent parser algorithm.
+    /// Errors if any bytes remain after the value (`TrailingData`).
+    pub fn parse(&mut self) -> Result<Bencode, BencodeError> {
+        let value = self.parse_value()?;
+        if self.tokenizer.byte_at(self.tokenizer.index).is_some() {
+            return Err(BencodeError::TrailingData(self.tokenizer.index));
         }
+        Ok(value)
     }
-    /// Parse the bencode data and return a Bencode enum
-    /// This implementation is using a recursive descent parser algorithm
-    pub fn parse(&mut self) -> Result<Bencode, String> {
+
+    fn parse_value(&mut self) -> Result<Bencode, BencodeError> {
         let start = self.tokenizer.index;
-        let next_token = self.tokenizer.next();
+        let next_token = self.tokenizer.next()?;
         let pos = Pos {
             start,
             end: self.tokenizer.index
         };
         match next_token {
-            Ok(Token::Int(value)) => Ok(Bencode::Int(value, pos)),
-            Ok(Token::String(value)) => Ok(Bencode::String(value.clone(), pos)),
-            Ok(Token::DictStart) => self.parse_dict(pos),
-            Ok(Token::ListStart) => self.parse_list(pos),
-            Err(e) => Err(format!("parse: {}", e)),
-            _ => Err("Unexpected token".to_string()),
+            Token::Int(value) => Ok(Bencode::Int(value, pos)),
+            Token::String(value) => Ok(Bencode::String(value, pos)),
+            Token::DictStart => self.parse_dict(pos),
+            Token::ListStart => self.parse_list(pos),
+            Token::ListDictEnd => Err(BencodeError::InvalidToken(start)),
         }
     }
 
-    fn parse_dict(&mut self, pos: Pos) -> Result<Bencode, String> {
+    fn parse_dict(&mut self, pos: Pos) -> Result<Bencode, BencodeError> {
         let mut dict = HashMap::new();
         loop {
-            let dict_key = match self.tokenizer.next() {
-                Ok(Token::String(key)) => match String::from_utf8(key) {
-                    Ok(value) => value,
-                    Err(e) => return Err(e.to_string()),
-                },
-                Ok(Token::ListDictEnd) => return Ok(Bencode::Dict(dict, Pos{end: self.idx(), ..pos})),
-                Err(e) => return Err(e),
-                _ => return Err("Unexpected token".to_string()),
-            };
             let start = self.idx();
-            let value_token = match self.tokenizer.next() {
-                Ok(Token::Int(value)) => Bencode::Int(value, Pos{start, end: self.idx()}),
-                Ok(Token::String(value)) => Bencode::String(value.clone(), Pos{end: self.idx(), start }),
-                Ok(Token::DictStart) => match self.parse_dict(Pos{start: self.tokenizer.index, end: 0}) {
-                    Ok(value) => value,
-                    Err(e) => return Err(e),
-                },
-                Ok(Token::ListStart) => match self.parse_list(Pos{start: self.tokenizer.index, end: 0}) {
-                    Ok(value) => value,
-                    Err(e) => return Err(e),
-                },
-                Err(e) => return Err(format!("parse_dict > parsing value for '{}' key: {}", dict_key, e)),
-                _ => return Err("Unexpected token".to_string()),
+            let dict_key = match self.tokenizer.next()? {
+                Token::String(key) => key,
+                Token::ListDictEnd => return Ok(Bencode::Dict(dict, Pos{end: self.idx(), ..pos})),
+                _ => return Err(BencodeError::InvalidToken(start)),
+            };
+            let value_start = self.idx();
+            let value_token = match self.tokenizer.next()? {
+                Token::Int(value) => Bencode::Int(value, Pos{start: value_start, end: self.idx()}),
+                Token::String(value) => Bencode::String(value, Pos{start: value_start, end: self.idx()}),
+                Token::DictStart => self.parse_dict(Pos{start: value_start, end: 0})?,
+                Token::ListStart => self.parse_list(Pos{start: value_start, end: 0})?,
+                Token::ListDictEnd => return Err(BencodeError::InvalidToken(value_start)),
             };
             dict.insert(dict_key, value_token);
         }
     }
-    fn parse_list(&mut self, pos: Pos) -> Result<Bencode, String> {
+    fn parse_list(&mut self, pos: Pos) -> Result<Bencode, BencodeError> {
         let mut list = Vec::new();
         loop {
             let start = self.tokenizer.index;
-            let token = match self.tokenizer.next() {
-                Ok(Token::Int(value)) => Bencode::Int(value, Pos{end: self.tokenizer.index, ..pos}),
-                Ok(Token::String(value)) => Bencode::String(value.clone(), Pos{ start, end: self.idx()}),
-                Ok(Token::DictStart) => match self.parse_dict(Pos{ start, end: 0}) {
-                    Ok(value) => value,
-                    Err(e) => return Err(e.clone()),
-                },
-                Ok(Token::ListStart) => match self.parse_list(Pos{start, end: 0}) {
-                    Ok(value) => value,
-                    Err(e) => return Err(e.clone()),
-                },
-                Ok(Token::ListDictEnd) => return Ok(Bencode::List(list, Pos{ end: self.idx(), ..pos})),
-                Err(e) => return Err(e.clone()),
+            let token = match self.tokenizer.next()? {
+                Token::Int(value) => Bencode::Int(value, Pos{ start, end: self.idx()}),
+                Token::String(value) => Bencode::String(value, Pos{ start, end: self.idx()}),
+                Token::DictStart => self.parse_dict(Pos{ start, end: 0})?,
+                Token::ListStart => self.parse_list(Pos{start, end: 0})?,
+                Token::ListDictEnd => return Ok(Bencode::List(list, Pos{ end: self.idx(), ..pos})),
             };
             list.push(token);
         }
@@ -246,8 +440,8 @@ mod tests {
         let bencode = parser.parse();
         assert_eq!(bencode, Ok(Bencode::Dict(
             vec![
-                ("cow".to_string(), Bencode::String(b"moo".to_vec(), Pos{start: 6, end: 11})),
-                ("spam".to_string(), Bencode::String(b"eggs".to_vec(), Pos{ start: 17, end: 23 }))
+                (b"cow".to_vec(), Bencode::String(b"moo".to_vec(), Pos{start: 6, end: 11})),
+                (b"spam".to_vec(), Bencode::String(b"eggs".to_vec(), Pos{ start: 17, end: 23 }))
             ].into_iter().collect(), Pos{start:0, end: 24}
         )));
     }
@@ -263,4 +457,120 @@ mod tests {
             ], Pos{start: 0, end: 14}
         )));
     }
+
+    #[test]
+    fn test_encode_int() {
+        assert_eq!(Bencode::Int(42, Pos{start:0,end:0}).encode(), b"i42e");
+        assert_eq!(Bencode::Int(-42, Pos{start:0,end:0}).encode(), b"i-42e");
+    }
+
+    #[test]
+    fn test_encode_string() {
+        assert_eq!(Bencode::String(b"spam".to_vec(), Pos{start:0,end:0}).encode(), b"4:spam");
+    }
+
+    #[test]
+    fn test_encode_list() {
+        let list = Bencode::List(
+            vec![
+                Bencode::String(b"spam".to_vec(), Pos{start:0,end:0}),
+                Bencode::String(b"eggs".to_vec(), Pos{start:0,end:0}),
+            ],
+            Pos{start:0,end:0},
+        );
+        assert_eq!(list.encode(), b"l4:spam4:eggse");
+    }
+
+    #[test]
+    fn test_encode_dict_sorts_keys() {
+        let dict = Bencode::Dict(
+            vec![
+                (b"spam".to_vec(), Bencode::String(b"eggs".to_vec(), Pos{start:0,end:0})),
+                (b"cow".to_vec(), Bencode::String(b"moo".to_vec(), Pos{start:0,end:0})),
+            ].into_iter().collect(),
+            Pos{start:0,end:0},
+        );
+        assert_eq!(dict.encode(), b"d3:cow3:moo4:spam4:eggse");
+    }
+
+    #[test]
+    fn test_round_trip_matches_original_bytes() {
+        let original = b"d8:announce4:http4:infod4:name4:test12:piece lengthi16384eee";
+        let mut reader = std::io::Cursor::new(original.to_vec());
+        let mut parser = Parser::new(&mut reader);
+        let bencode = parser.parse().unwrap();
+        assert_eq!(bencode.encode(), original.to_vec());
+    }
+
+    #[test]
+    fn test_round_trip_matches_original_bytes_with_non_utf8_keys() {
+        // Two distinct non-UTF-8 4-byte keys that both decode to the same
+        // U+FFFD under lossy UTF-8 decoding must still round-trip as two
+        // separate dict entries, not collapse into one.
+        let original = b"d4:\xfe\xfe\xfe\xfe4:key24:\xff\xff\xff\xff4:key1e";
+        let mut reader = std::io::Cursor::new(original.to_vec());
+        let mut parser = Parser::new(&mut reader);
+        let bencode = parser.parse().unwrap();
+        match &bencode {
+            Bencode::Dict(dict, _) => assert_eq!(dict.len(), 2),
+            _ => panic!("expected a dict"),
+        }
+        assert_eq!(bencode.encode(), original.to_vec());
+    }
+
+    #[test]
+    fn test_decode_rejects_leading_zero_string_length() {
+        let mut reader = std::io::Cursor::new("04:spam");
+        let mut parser = Parser::new(&mut reader);
+        assert_eq!(parser.parse(), Err(BencodeError::InvalidStringLength(0)));
+    }
+
+    #[test]
+    fn test_decode_rejects_out_of_bounds_string_length() {
+        let mut reader = std::io::Cursor::new("10:spam");
+        let mut parser = Parser::new(&mut reader);
+        assert_eq!(parser.parse(), Err(BencodeError::StringOutOfBounds(0)));
+    }
+
+    #[test]
+    fn test_decode_rejects_trailing_data() {
+        let mut reader = std::io::Cursor::new("i42egarbage");
+        let mut parser = Parser::new(&mut reader);
+        assert_eq!(parser.parse(), Err(BencodeError::TrailingData(4)));
+    }
+
+    #[test]
+    fn test_decode_rejects_string_length_over_max_alloc() {
+        let mut reader = std::io::Cursor::new("999999999999:spam");
+        let mut parser = Parser::with_max_alloc(&mut reader, 1024);
+        assert_eq!(parser.parse(), Err(BencodeError::AllocationTooLarge(0)));
+    }
+
+    #[test]
+    fn test_decode_streams_from_reader_larger_than_fill_chunk() {
+        let value = "a".repeat(FILL_CHUNK * 2 + 7);
+        let encoded = format!("{}:{}", value.len(), value);
+        let mut reader = std::io::Cursor::new(encoded);
+        let mut parser = Parser::new(&mut reader);
+        let bencode = parser.parse().unwrap();
+        assert_eq!(bencode, Bencode::String(value.into_bytes(), Pos{start: 0, end: parser.raw().len()}));
+    }
+
+    #[test]
+    fn test_decode_rejects_unterminated_string_length_digit_run() {
+        // 21 digits with no ':' ever arriving: bounded by MAX_LENGTH_DIGITS
+        // instead of buffering digits from the reader forever.
+        let mut reader = std::io::Cursor::new("1".repeat(21));
+        let mut parser = Parser::new(&mut reader);
+        assert_eq!(parser.parse(), Err(BencodeError::InvalidStringLength(0)));
+    }
+
+    #[test]
+    fn test_decode_rejects_unterminated_int_digit_run() {
+        // 21 digits with no 'e' ever arriving: bounded by MAX_INT_DIGITS
+        // instead of buffering digits from the reader forever.
+        let mut reader = std::io::Cursor::new(format!("i{}", "1".repeat(21)));
+        let mut parser = Parser::new(&mut reader);
+        assert_eq!(parser.parse(), Err(BencodeError::InvalidInteger(0)));
+    }
 }
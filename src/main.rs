@@ -1,5 +1,7 @@
 use clap::{Parser, Subcommand};
 mod bencode;
+mod sha1;
+mod sha256;
 mod torrent;
 
 #[derive(Parser)]
@@ -24,6 +26,16 @@ enum Commands {
         /// The torrent file to parse
         #[arg(short, long, value_name = "FILE")]
         file: std::path::PathBuf,
+    },
+    /// verify downloaded data against the piece hashes in the torrent file
+    Verify {
+        /// The torrent file to parse
+        #[arg(short, long, value_name = "FILE")]
+        file: std::path::PathBuf,
+        /// The downloaded data: a directory for multi-file torrents, or the
+        /// data file itself for single-file torrents
+        #[arg(short, long, value_name = "CONTENT")]
+        content: std::path::PathBuf,
     }
 }
 
@@ -38,9 +50,38 @@ fn main() {
                 let mut parser = bencode::Parser::new(&mut reader);
                 match parser.parse() {
                     Ok(data) => {
-                        let data = torrent::TorrentFile::from_bencode(&data).unwrap();
+                        let data = match torrent::TorrentFile::from_bencode(&data) {
+                            Ok(data) => data,
+                            Err(e) => {
+                                eprintln!("Error parsing torrent: {:?}", e);
+                                return;
+                            }
+                        };
                         println!("");
                         println!("announce: \"{}\"", data.announce);
+                        if let Some(announce_list) = &data.announce_list {
+                            println!("announce-list:");
+                            for (tier, trackers) in announce_list.iter().enumerate() {
+                                println!("   tier {}: {:?}", tier, trackers);
+                            }
+                        }
+                        let info_hash = data.info_hash(parser.raw());
+                        let info_hash_hex: String = info_hash.iter().map(|b| format!("{:02x}", b)).collect();
+                        println!("info_hash: {} ({})", info_hash_hex, torrent::percent_encode(&info_hash));
+                        if let Some(info_hash_v2) = data.info_hash_v2(parser.raw()) {
+                            let info_hash_v2_hex: String = info_hash_v2.iter().map(|b| format!("{:02x}", b)).collect();
+                            println!("info_hash_v2: {}", info_hash_v2_hex);
+                        }
+                        println!("magnet_link: {}", data.magnet_link(parser.raw()));
+                        if let Some(meta_version) = data.info.meta_version {
+                            println!("meta_version: {}", meta_version);
+                        }
+                        if let Some(file_tree) = &data.info.file_tree {
+                            println!("file_tree: {} file(s)", file_tree.file_count());
+                        }
+                        if let Some(piece_layers) = &data.piece_layers {
+                            println!("piece_layers: {} entry(s)", piece_layers.len());
+                        }
                         println!("info:");
                         if let Some(l) = data.info.length {
                             println!("  length: {}", l);
@@ -71,6 +112,51 @@ fn main() {
                         eprintln!("Error parsing file: {:?}", e);
                     }
                 }
+            },
+            Commands::Verify { file, content } => {
+                println!("Parsing file: {:?}", file);
+                let mut reader = std::io::BufReader::new(std::fs::File::open(file).unwrap());
+                let mut parser = bencode::Parser::new(&mut reader);
+                match parser.parse() {
+                    Ok(data) => {
+                        let data = match torrent::TorrentFile::from_bencode(&data) {
+                            Ok(data) => data,
+                            Err(e) => {
+                                eprintln!("Error parsing torrent: {:?}", e);
+                                return;
+                            }
+                        };
+                        match data.verify(&content) {
+                            Ok(reports) => {
+                                let mut failed = 0;
+                                for report in &reports {
+                                    if report.ok {
+                                        println!("piece {}: OK", report.index);
+                                    } else {
+                                        failed += 1;
+                                        println!("piece {}: FAILED", report.index);
+                                        for overlap in &report.overlaps {
+                                            println!(
+                                                "    - {:?} [{}..{}]",
+                                                overlap.path,
+                                                overlap.offset,
+                                                overlap.offset + overlap.length
+                                            );
+                                        }
+                                    }
+                                }
+                                println!();
+                                println!("{}/{} pieces OK", reports.len() - failed, reports.len());
+                            },
+                            Err(e) => {
+                                eprintln!("Error verifying content: {:?}", e);
+                            }
+                        }
+                    },
+                    Err(e) => {
+                        eprintln!("Error parsing file: {:?}", e);
+                    }
+                }
             }
         }
     }
@@ -1,16 +1,63 @@
 use std::{collections::HashMap, fmt::{self, Debug}};
+use std::fs::File as FsFile;
+use std::io::{Read, Seek, SeekFrom};
+use std::path::{Path, PathBuf};
 
-use super::bencode::Bencode;
+use super::bencode::{Bencode, BencodeError, Pos};
+use super::sha1::sha1;
+use super::sha256::sha256;
+
+/// A `.torrent` decoding failure: either the bencode underneath was
+/// malformed (`DecodeError`), or it decoded fine but didn't match the
+/// torrent schema (`MissingField`/`WrongType`).
+#[derive(Debug, PartialEq, Clone)]
+pub enum TorrentError {
+    MissingField(&'static str),
+    WrongType { key: &'static str, expected: &'static str },
+    DecodeError(BencodeError),
+}
+
+impl fmt::Display for TorrentError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            TorrentError::MissingField(key) => write!(f, "missing field '{}'", key),
+            TorrentError::WrongType { key, expected } => {
+                write!(f, "field '{}' has wrong type, expected {}", key, expected)
+            },
+            TorrentError::DecodeError(e) => write!(f, "bencode decode error: {}", e),
+        }
+    }
+}
+
+impl std::error::Error for TorrentError {}
+
+impl From<BencodeError> for TorrentError {
+    fn from(e: BencodeError) -> Self {
+        TorrentError::DecodeError(e)
+    }
+}
 
 #[derive(Debug)]
 #[allow(dead_code)]
 pub struct TorrentFile {
     pub announce: String,
+    /// `announce-list`: a tiered list of tracker URLs, preserving tier
+    /// grouping, for multi-tracker torrents.
+    pub announce_list: Option<Vec<Vec<String>>>,
     pub created_by: Option<String>,
     pub creation_date: Option<i64>,
     pub info: Info,
+    /// v2 `piece layers`: maps a file's raw 32-byte "pieces root"
+    /// (`file_tree` leaf) to its concatenated SHA-256 leaf hashes. Keys are
+    /// kept as raw bytes rather than decoded to `String`, since a pieces
+    /// root is binary, not UTF-8 text.
+    pub piece_layers: Option<PieceLayers>,
+    info_pos: Pos,
 }
 
+/// Maps a raw 32-byte "pieces root" to its concatenated SHA-256 leaf hashes.
+pub type PieceLayers = HashMap<Vec<u8>, Vec<u8>>;
+
 #[derive(Debug)]
 #[allow(dead_code)]
 pub struct Info {
@@ -19,6 +66,58 @@ pub struct Info {
     pub name: String,
     pub piece_length: i64,
     pub pieces: Pieces,
+    /// `meta version`: 2 for v2-only torrents, absent/1 for v1, 2 alongside
+    /// v1 `pieces`/`files` for hybrid torrents.
+    pub meta_version: Option<i64>,
+    /// v2 `file tree`, present for v2 and hybrid torrents.
+    pub file_tree: Option<FileNode>,
+}
+
+/// A node of the v2 `file tree`: either a directory of further named nodes,
+/// or a leaf file carrying its length and (for non-empty files) the root of
+/// its merkle piece-hash tree.
+#[derive(Debug)]
+#[allow(dead_code)]
+pub enum FileNode {
+    Dir(HashMap<String, FileNode>),
+    File {
+        length: i64,
+        pieces_root: Option<[u8; 32]>,
+    },
+}
+
+impl FileNode {
+    /// The number of file leaves under this node (1 for a `File`, the sum
+    /// over children for a `Dir`, recursing through the whole subtree),
+    /// useful for a quick summary of a v2 `file tree`.
+    pub fn file_count(&self) -> usize {
+        match self {
+            FileNode::File { .. } => 1,
+            FileNode::Dir(children) => children.values().map(FileNode::file_count).sum(),
+        }
+    }
+
+    fn from_bencode(data: &Bencode) -> Result<FileNode, TorrentError> {
+        match data {
+            Bencode::Dict(entries, _) => match entries.get(b"".as_slice()) {
+                Some(Bencode::Dict(leaf, _)) => Ok(FileNode::File {
+                    length: extract_non_negative_i64(leaf, "length")?,
+                    pieces_root: extract_optional_pieces_root(leaf)?,
+                }),
+                Some(_) => Err(TorrentError::WrongType { key: "", expected: "dict" }),
+                None => {
+                    let mut children = HashMap::new();
+                    for (name, child) in entries {
+                        let name = String::from_utf8(name.clone())
+                            .map_err(|_| TorrentError::WrongType { key: "file tree", expected: "UTF-8 name" })?;
+                        children.insert(name, FileNode::from_bencode(child)?);
+                    }
+                    Ok(FileNode::Dir(children))
+                },
+            },
+            _ => Err(TorrentError::WrongType { key: "file tree", expected: "dict" }),
+        }
+    }
 }
 
 pub struct Pieces(pub Vec<[u8; 20]>);
@@ -41,118 +140,335 @@ pub struct File {
 }
 
 impl TorrentFile {
-    pub fn from_bencode(data: &Bencode) -> Result<TorrentFile, String> {
+    pub fn from_bencode(data: &Bencode) -> Result<TorrentFile, TorrentError> {
         match data {
-            Bencode::Dict(data, _) => Ok(TorrentFile {
-                announce: extract_string(data, "announce")?,
-                creation_date: extract_option_i64(data, "creation date")?,
-                created_by: extract_optional_string(data, "created by")?,
-                info: match data.get("info") {
-                    Some(info) => match Info::from_bencode(info) {
-                        Ok(info) => info,
-                        Err(e) => return Err(e),
+            Bencode::Dict(data, _) => {
+                let info_bencode = match data.get(b"info".as_slice()) {
+                    Some(info) => info,
+                    None => return Err(TorrentError::MissingField("info")),
+                };
+                let info_pos = info_bencode.pos();
+                let info_pos = Pos { start: info_pos.start, end: info_pos.end };
+                Ok(TorrentFile {
+                    announce: extract_string(data, "announce")?,
+                    announce_list: extract_announce_list(data)?,
+                    creation_date: extract_option_i64(data, "creation date")?,
+                    created_by: extract_optional_string(data, "created by")?,
+                    info: Info::from_bencode(info_bencode)?,
+                    piece_layers: extract_piece_layers(data)?,
+                    info_pos,
+                })
+            },
+            _ => Err(TorrentError::WrongType { key: "", expected: "dict" }),
+        }
+    }
+
+    /// Compute the v1 info-hash: the SHA-1 of the raw, byte-for-byte `info`
+    /// value as it appeared in `raw` (not a re-encoding), so non-canonical
+    /// inputs still hash correctly.
+    pub fn info_hash(&self, raw: &[u8]) -> [u8; 20] {
+        sha1(&raw[self.info_pos.start..self.info_pos.end])
+    }
+
+    /// Compute the v2 info-hash: the SHA-256 of the raw `info` value,
+    /// present whenever `info.meta_version` is 2 (pure v2 or hybrid
+    /// torrents).
+    pub fn info_hash_v2(&self, raw: &[u8]) -> Option<[u8; 32]> {
+        if self.info.meta_version != Some(2) {
+            return None;
+        }
+        Some(sha256(&raw[self.info_pos.start..self.info_pos.end]))
+    }
+
+    /// Build a `magnet:?xt=urn:...` link carrying the display name and every
+    /// tracker from `announce-list`, or the `announce` tracker when that list
+    /// is absent or holds no trackers; `announce` is not added on its own
+    /// when `announce-list` has trackers. Torrents with v1 `pieces`
+    /// (v1-only or hybrid) are keyed by the v1 info-hash as `urn:btih:...`;
+    /// a pure v2 torrent has no v1 `pieces` to hash, so it falls back to the
+    /// v2 info-hash as a `urn:btmh:...` multihash (type `0x12`, SHA-256).
+    pub fn magnet_link(&self, raw: &[u8]) -> String {
+        let xt = match self.info_hash_v2(raw) {
+            Some(v2_hash) if self.info.pieces.0.is_empty() => {
+                let mut multihash = vec![0x12u8, 0x20u8];
+                multihash.extend_from_slice(&v2_hash);
+                let hash_hex: String = multihash.iter().map(|b| format!("{:02x}", b)).collect();
+                format!("urn:btmh:{}", hash_hex)
+            },
+            _ => {
+                let hash_hex: String = self.info_hash(raw).iter().map(|b| format!("{:02x}", b)).collect();
+                format!("urn:btih:{}", hash_hex)
+            },
+        };
+        let mut link = format!("magnet:?xt={}&dn={}", xt, url_encode(&self.info.name));
+        let trackers: Vec<&String> = match &self.announce_list {
+            Some(tiers) => tiers.iter().flatten().collect(),
+            None => vec![],
+        };
+        if trackers.is_empty() {
+            // `announce-list` is either absent or present with only empty
+            // tiers (e.g. `13:announce-listlee`); either way `announce`
+            // is still a valid tracker and shouldn't be dropped.
+            link.push_str(&format!("&tr={}", url_encode(&self.announce)));
+        } else {
+            for tracker in trackers {
+                link.push_str(&format!("&tr={}", url_encode(tracker)));
+            }
+        }
+        link
+    }
+
+    /// Walk the downloaded payload under `content_root` as one logical byte
+    /// stream, split it into `piece_length` chunks, and check each chunk's
+    /// SHA-1 against `info.pieces`. For multi-file torrents `content_root`
+    /// is the directory `info.files` paths are relative to; for single-file
+    /// torrents it is the data file itself.
+    pub fn verify(&self, content_root: &Path) -> std::io::Result<Vec<PieceReport>> {
+        let layout = self.file_layout(content_root);
+        let total_length: u64 = layout.iter().map(|(_, length)| length).sum();
+        let piece_length = self.info.piece_length as u64;
+
+        let mut reports = Vec::with_capacity(self.info.pieces.0.len());
+        let mut offset: u64 = 0;
+        for (index, expected) in self.info.pieces.0.iter().enumerate() {
+            let length = piece_length.min(total_length.saturating_sub(offset));
+            let overlaps = overlapping_files(&layout, offset, length);
+
+            // A missing or short-read file is the common case for an
+            // in-progress/partial download, not a reason to abort the
+            // whole run: mark this piece (and whatever it overlaps) as
+            // not ok and keep checking the rest.
+            let mut actual = Vec::with_capacity(length as usize);
+            let mut read_ok = true;
+            for overlap in &overlaps {
+                match read_overlap(overlap) {
+                    Ok(chunk) => actual.extend_from_slice(&chunk),
+                    Err(_) => {
+                        read_ok = false;
+                        break;
                     },
-                    None => return Err(String::from("Missing info")),
-                },
-            }),
-            _ => return Err(String::from("Expected dictionary")),
+                }
+            }
+
+            reports.push(PieceReport {
+                index,
+                ok: read_ok && sha1(&actual) == *expected,
+                overlaps,
+            });
+            offset += length;
+        }
+        Ok(reports)
+    }
+
+    /// Resolve `info.files`/`info.length` into an ordered list of
+    /// `(path, length)` pairs describing the logical byte stream.
+    fn file_layout(&self, content_root: &Path) -> Vec<(PathBuf, u64)> {
+        match &self.info.files {
+            Some(files) => files
+                .iter()
+                .map(|f| {
+                    let mut path = content_root.to_path_buf();
+                    for part in &f.path {
+                        path.push(part);
+                    }
+                    (path, f.length as u64)
+                })
+                .collect(),
+            None => vec![(content_root.to_path_buf(), self.info.length.unwrap_or(0) as u64)],
+        }
+    }
+}
+
+/// Pass/fail outcome for a single piece, plus the files and byte offsets
+/// within them that the piece overlaps, so a caller can report *which*
+/// file is corrupt rather than just that verification failed.
+#[derive(Debug)]
+pub struct PieceReport {
+    pub index: usize,
+    pub ok: bool,
+    pub overlaps: Vec<FileOverlap>,
+}
+
+/// The byte range `[offset, offset + length)` within `path` that a piece
+/// overlaps.
+#[derive(Debug)]
+pub struct FileOverlap {
+    pub path: PathBuf,
+    pub offset: u64,
+    pub length: u64,
+}
+
+fn overlapping_files(layout: &[(PathBuf, u64)], offset: u64, length: u64) -> Vec<FileOverlap> {
+    let mut overlaps = Vec::new();
+    let mut file_start = 0u64;
+    let range_end = offset + length;
+    for (path, file_length) in layout {
+        let file_end = file_start + file_length;
+        if file_start < range_end && file_end > offset {
+            let overlap_start = offset.max(file_start);
+            let overlap_end = range_end.min(file_end);
+            overlaps.push(FileOverlap {
+                path: path.clone(),
+                offset: overlap_start - file_start,
+                length: overlap_end - overlap_start,
+            });
         }
-        
+        file_start = file_end;
     }
+    overlaps
+}
+
+/// Read the bytes one `FileOverlap` covers. Any IO failure (missing file,
+/// short read on a truncated/partial download, ...) is the caller's
+/// signal to mark that piece not ok rather than abort `verify()` outright.
+fn read_overlap(overlap: &FileOverlap) -> std::io::Result<Vec<u8>> {
+    let mut f = FsFile::open(&overlap.path)?;
+    f.seek(SeekFrom::Start(overlap.offset))?;
+    let mut chunk = vec![0u8; overlap.length as usize];
+    f.read_exact(&mut chunk)?;
+    Ok(chunk)
 }
+
 impl Info {
-    fn from_bencode(data: &Bencode) -> Result<Info, String> {
+    fn from_bencode(data: &Bencode) -> Result<Info, TorrentError> {
         match data {
             Bencode::Dict(data, _) => Ok(Info {
-                files: match data.get("files") {
+                files: match data.get(b"files".as_slice()) {
                     Some(Bencode::List(b_files, _)) => {
                         let mut files = vec![];
                         for file in b_files {
-                            match File::from_bencode(file) {
-                                Ok(file) => files.push(file),
-                                Err(_) => return Err(String::from("Invalid file")),
-                            }
+                            files.push(File::from_bencode(file)?);
                         }
                         Some(files)
                     },
-                    Some(_) => return Err(String::from("Invalid files type")),
+                    Some(_) => return Err(TorrentError::WrongType { key: "files", expected: "list" }),
                     None => None,
                 },
-                length: extract_option_i64(data, "length")?,
+                length: extract_option_non_negative_i64(data, "length")?,
                 name: extract_string(data, "name")?,
-                piece_length: extract_i64(data, "piece length")?,
+                piece_length: extract_non_negative_i64(data, "piece length")?,
                 pieces: extract_pieces(data)?,
+                meta_version: extract_option_i64(data, "meta version")?,
+                file_tree: match data.get(b"file tree".as_slice()) {
+                    Some(file_tree) => Some(FileNode::from_bencode(file_tree)?),
+                    None => None,
+                },
             }),
-            _ => Err(String::from("Expected dictionary for info")),
+            _ => Err(TorrentError::WrongType { key: "info", expected: "dict" }),
         }
     }
 }
 
 impl File {
-    fn from_bencode(data: &Bencode) -> Result<File, String> {
+    fn from_bencode(data: &Bencode) -> Result<File, TorrentError> {
         match data {
             Bencode::Dict(data, _) => Ok(File {
-                length: extract_i64(data, "length")?,
-                path: match data.get("path") {
+                length: extract_non_negative_i64(data, "length")?,
+                path: match data.get(b"path".as_slice()) {
                     Some(Bencode::List(p, _)) => {
                         let mut paths = vec![];
                         for p in p {
                             match p {
                                 Bencode::String(s, _) => match String::from_utf8(s.clone()) {
-                                    Ok(s) => paths.push(s),
-                                    Err(_) => return Err(String::from("Invalid path string")),
+                                    Ok(s) => {
+                                        // `verify()` joins these components onto a
+                                        // `content_root` directory; an empty, ".",
+                                        // ".." or separator-bearing component would
+                                        // let a crafted torrent walk outside it.
+                                        if s.is_empty() || s == "." || s == ".."
+                                            || s.contains('/') || s.contains('\\') {
+                                            return Err(TorrentError::WrongType { key: "path", expected: "safe path component" });
+                                        }
+                                        paths.push(s)
+                                    },
+                                    Err(_) => return Err(TorrentError::WrongType { key: "path", expected: "UTF-8 string" }),
                                 },
-                                _ => return Err(String::from("Invalid path type")),
+                                _ => return Err(TorrentError::WrongType { key: "path", expected: "string" }),
                             }
                         }
                         paths
                     },
-                    _ => return Err(String::from("Invalid path, expected list")),
+                    _ => return Err(TorrentError::WrongType { key: "path", expected: "list" }),
                 },
             }),
-            _ => Err(String::from("Expected dictionary for file")),
+            _ => Err(TorrentError::WrongType { key: "files[]", expected: "dict" }),
         }
     }
 }
 
 // helper functions
 
-fn extract_string(data: &HashMap<String, Bencode>, key: &str) -> Result<String, String> {
-    match data.get(key) {
+fn extract_string(data: &HashMap<Vec<u8>, Bencode>, key: &'static str) -> Result<String, TorrentError> {
+    match data.get(key.as_bytes()) {
         Some(Bencode::String(s, _)) => match String::from_utf8(s.clone()) {
             Ok(s) => Ok(s),
-            Err(_) => return Err(String::from("Invalid announce string")),
+            Err(_) => Err(TorrentError::WrongType { key, expected: "UTF-8 string" }),
         },
-        _ => return Err(String::from("Invalid announce string")),
+        Some(_) => Err(TorrentError::WrongType { key, expected: "string" }),
+        None => Err(TorrentError::MissingField(key)),
     }
 }
-fn extract_optional_string(data: &HashMap<String, Bencode>, key: &str) -> Result<Option<String>, String> {
-    match data.get(key) {
-        Some(created_by) => match created_by {
-            Bencode::String(s, _) => match String::from_utf8(s.clone()) {
-                Ok(s) => Ok(Some(s.clone())),
-                _ => return Err(String::from("Invalid string")),
-            },
-            _ => return Err(String::from("Invalid created by type")),
+fn extract_optional_string(data: &HashMap<Vec<u8>, Bencode>, key: &'static str) -> Result<Option<String>, TorrentError> {
+    match data.get(key.as_bytes()) {
+        Some(Bencode::String(s, _)) => match String::from_utf8(s.clone()) {
+            Ok(s) => Ok(Some(s)),
+            Err(_) => Err(TorrentError::WrongType { key, expected: "UTF-8 string" }),
         },
+        Some(_) => Err(TorrentError::WrongType { key, expected: "string" }),
         None => Ok(None),
     }
 }
-fn extract_i64(data: &HashMap<String, Bencode>, key: &str) -> Result<i64, String> {
-    match data.get(key) {
-        Some(Bencode::Int(i,_)) => Ok(i.clone()),
-        _ => return Err(String::from("Invalid i64")),
+fn extract_i64(data: &HashMap<Vec<u8>, Bencode>, key: &'static str) -> Result<i64, TorrentError> {
+    match data.get(key.as_bytes()) {
+        Some(Bencode::Int(i, _)) => Ok(*i),
+        Some(_) => Err(TorrentError::WrongType { key, expected: "integer" }),
+        None => Err(TorrentError::MissingField(key)),
     }
 }
 
-fn extract_option_i64(data: &HashMap<String, Bencode>, key: &str) -> Result<Option<i64>, String> {
-    match data.get(key) {
-        Some(Bencode::Int(i,_)) => Ok(Some(i.clone())),
-        Some(_) => return Err(String::from("Invalid option type")),
+fn extract_option_i64(data: &HashMap<Vec<u8>, Bencode>, key: &'static str) -> Result<Option<i64>, TorrentError> {
+    match data.get(key.as_bytes()) {
+        Some(Bencode::Int(i, _)) => Ok(Some(*i)),
+        Some(_) => Err(TorrentError::WrongType { key, expected: "integer" }),
         None => Ok(None),
     }
 }
 
+/// Like `extract_i64`, but rejects negative values: `length`/`piece
+/// length` feed `file_layout`/`verify`'s unsigned byte-offset arithmetic,
+/// where a negative value would wrap on cast to `u64` and panic on
+/// overflow rather than fail cleanly.
+fn extract_non_negative_i64(data: &HashMap<Vec<u8>, Bencode>, key: &'static str) -> Result<i64, TorrentError> {
+    let value = extract_i64(data, key)?;
+    if value < 0 {
+        return Err(TorrentError::WrongType { key, expected: "non-negative integer" });
+    }
+    Ok(value)
+}
+
+/// `Option` counterpart of `extract_non_negative_i64`.
+fn extract_option_non_negative_i64(data: &HashMap<Vec<u8>, Bencode>, key: &'static str) -> Result<Option<i64>, TorrentError> {
+    match extract_option_i64(data, key)? {
+        Some(value) if value < 0 => Err(TorrentError::WrongType { key, expected: "non-negative integer" }),
+        other => Ok(other),
+    }
+}
+
+/// Percent-encode a string for use as a magnet link query value, leaving
+/// unreserved characters (RFC 3986) untouched.
+fn url_encode(s: &str) -> String {
+    let mut encoded = String::with_capacity(s.len());
+    for byte in s.bytes() {
+        match byte {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'_' | b'.' | b'~' => {
+                encoded.push(byte as char);
+            },
+            _ => encoded.push_str(&format!("%{:02X}", byte)),
+        }
+    }
+    encoded
+}
+
 pub fn percent_encode(bytes: &[u8; 20]) -> String {
     let mut encoded = String::with_capacity(bytes.len() * 3);
     for &byte in bytes {
@@ -162,23 +478,308 @@ pub fn percent_encode(bytes: &[u8; 20]) -> String {
     encoded
 }
 
-fn extract_pieces(data: &HashMap<String, Bencode>) -> Result<Pieces, String> {
-    let mut pieces = vec![];
-    match data.get("pieces") {
+fn extract_announce_list(data: &HashMap<Vec<u8>, Bencode>) -> Result<Option<Vec<Vec<String>>>, TorrentError> {
+    match data.get(b"announce-list".as_slice()) {
+        Some(Bencode::List(tiers, _)) => {
+            let mut announce_list = vec![];
+            for tier in tiers {
+                match tier {
+                    Bencode::List(trackers, _) => {
+                        let mut tier_urls = vec![];
+                        for tracker in trackers {
+                            match tracker {
+                                Bencode::String(s, _) => match String::from_utf8(s.clone()) {
+                                    Ok(s) => tier_urls.push(s),
+                                    Err(_) => return Err(TorrentError::WrongType { key: "announce-list", expected: "UTF-8 string" }),
+                                },
+                                _ => return Err(TorrentError::WrongType { key: "announce-list", expected: "string" }),
+                            }
+                        }
+                        announce_list.push(tier_urls);
+                    },
+                    _ => return Err(TorrentError::WrongType { key: "announce-list", expected: "list" }),
+                }
+            }
+            Ok(Some(announce_list))
+        },
+        Some(_) => Err(TorrentError::WrongType { key: "announce-list", expected: "list" }),
+        None => Ok(None),
+    }
+}
+
+fn extract_optional_pieces_root(data: &HashMap<Vec<u8>, Bencode>) -> Result<Option<[u8; 32]>, TorrentError> {
+    match data.get(b"pieces root".as_slice()) {
+        Some(Bencode::String(s, _)) => {
+            let root: [u8; 32] = s.clone().try_into()
+                .map_err(|_| TorrentError::WrongType { key: "pieces root", expected: "32 bytes" })?;
+            Ok(Some(root))
+        },
+        Some(_) => Err(TorrentError::WrongType { key: "pieces root", expected: "string" }),
+        None => Ok(None),
+    }
+}
+
+fn extract_piece_layers(data: &HashMap<Vec<u8>, Bencode>) -> Result<Option<PieceLayers>, TorrentError> {
+    match data.get(b"piece layers".as_slice()) {
+        Some(Bencode::Dict(layers, _)) => {
+            let mut result = HashMap::new();
+            for (pieces_root, hashes) in layers {
+                match hashes {
+                    Bencode::String(s, _) => result.insert(pieces_root.clone(), s.clone()),
+                    _ => return Err(TorrentError::WrongType { key: "piece layers", expected: "string" }),
+                };
+            }
+            Ok(Some(result))
+        },
+        Some(_) => Err(TorrentError::WrongType { key: "piece layers", expected: "dict" }),
+        None => Ok(None),
+    }
+}
+
+fn extract_pieces(data: &HashMap<Vec<u8>, Bencode>) -> Result<Pieces, TorrentError> {
+    match data.get(b"pieces".as_slice()) {
         Some(Bencode::String(s, _)) => {
-            let mut i = 0;
-            while i < s.len() {
-                let piece: [u8; 20] = s[i..i+20].try_into().map_err(|_| "Invalid piece length")?;
-                pieces.push(piece);
-                i += 20;
+            if s.len() % 20 != 0 {
+                return Err(TorrentError::WrongType { key: "pieces", expected: "multiple of 20 bytes" });
             }
-            Ok(Pieces(pieces) )
+            let pieces = s.chunks_exact(20).map(|chunk| chunk.try_into().unwrap()).collect();
+            Ok(Pieces(pieces))
         },
-        Some(_) => return Err(String::from("Invalid pieces type")),
-        None => Ok(Pieces(pieces)),
-    }   
+        Some(_) => Err(TorrentError::WrongType { key: "pieces", expected: "string" }),
+        None => Ok(Pieces(vec![])),
+    }
 }
 
-fn _sha1_from_torrent_file(_data: &str) -> &[u8; 20] {
-    todo!()
-}
\ No newline at end of file
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use super::super::bencode::Parser;
+
+    fn parse_torrent(raw: &[u8]) -> (TorrentFile, Vec<u8>) {
+        let mut reader = std::io::Cursor::new(raw.to_vec());
+        let mut parser = Parser::new(&mut reader);
+        let bencode = parser.parse().unwrap();
+        let torrent = TorrentFile::from_bencode(&bencode).unwrap();
+        (torrent, parser.raw().to_vec())
+    }
+
+    #[test]
+    fn test_info_hash_matches_known_fixture() {
+        let announce = "http://tracker.test/";
+        let name = "test.txt";
+        let pieces = [0u8; 20];
+        let mut raw = format!(
+            "d8:announce{}:{}4:infod6:lengthi10e4:name{}:{}12:piece lengthi16384e6:pieces{}:",
+            announce.len(), announce, name.len(), name, pieces.len()
+        ).into_bytes();
+        raw.extend_from_slice(&pieces);
+        raw.extend_from_slice(b"ee");
+
+        let (torrent, raw) = parse_torrent(&raw);
+        let hash = torrent.info_hash(&raw);
+        let hex: String = hash.iter().map(|b| format!("{:02x}", b)).collect();
+        assert_eq!(hex, "e87aab8e0c46e92e2d508901a16a52c58dbabb29");
+    }
+
+    #[test]
+    fn test_verify_reports_which_file_is_corrupt() {
+        // Two files, "a.bin" (5 bytes) and "b.bin" (3 bytes), laid out as
+        // one logical stream "AAAAABBB" split into two 4-byte pieces: the
+        // second piece straddles both files.
+        let piece0 = sha1(b"AAAA");
+        let piece1 = sha1(b"ABBB");
+        let mut pieces = Vec::new();
+        pieces.extend_from_slice(&piece0);
+        pieces.extend_from_slice(&piece1);
+
+        let mut raw = format!(
+            "d8:announce4:http4:infod5:filesld6:lengthi5e4:pathl5:a.binee\
+             d6:lengthi3e4:pathl5:b.bineee4:name4:test12:piece lengthi4e6:pieces{}:",
+            pieces.len()
+        ).into_bytes();
+        raw.extend_from_slice(&pieces);
+        raw.extend_from_slice(b"ee");
+
+        let (torrent, _) = parse_torrent(&raw);
+
+        let dir = std::env::temp_dir().join(format!("torrent_test_verify_{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        std::fs::write(dir.join("a.bin"), b"AAAAA").unwrap();
+        std::fs::write(dir.join("b.bin"), b"CCC").unwrap(); // corrupted: should be "BBB"
+
+        let reports = torrent.verify(&dir).unwrap();
+        std::fs::remove_dir_all(&dir).unwrap();
+
+        assert_eq!(reports.len(), 2);
+        assert!(reports[0].ok);
+        assert!(!reports[1].ok);
+        assert_eq!(reports[1].overlaps.len(), 2);
+        assert_eq!(reports[1].overlaps[0].path, dir.join("a.bin"));
+        assert_eq!(reports[1].overlaps[0].offset, 4);
+        assert_eq!(reports[1].overlaps[0].length, 1);
+        assert_eq!(reports[1].overlaps[1].path, dir.join("b.bin"));
+        assert_eq!(reports[1].overlaps[1].offset, 0);
+        assert_eq!(reports[1].overlaps[1].length, 3);
+    }
+
+    #[test]
+    fn test_parses_v2_file_tree_and_piece_layers() {
+        let pieces_root = [0x11u8; 32];
+        let layer_hashes = [0x22u8; 32];
+
+        let mut file_tree = b"d8:test.txtd0:d6:lengthi10e11:pieces root32:".to_vec();
+        file_tree.extend_from_slice(&pieces_root);
+        file_tree.extend_from_slice(b"eee");
+
+        let mut raw = b"d8:announce4:http4:infod9:file tree".to_vec();
+        raw.extend_from_slice(&file_tree);
+        raw.extend_from_slice(b"12:meta versioni2e4:name4:test12:piece lengthi16384ee12:piece layersd32:");
+        raw.extend_from_slice(&pieces_root);
+        raw.extend_from_slice(b"32:");
+        raw.extend_from_slice(&layer_hashes);
+        raw.extend_from_slice(b"ee");
+
+        let (torrent, _) = parse_torrent(&raw);
+
+        assert_eq!(torrent.info.meta_version, Some(2));
+        match &torrent.info.file_tree {
+            Some(FileNode::Dir(children)) => {
+                assert_eq!(children.len(), 1);
+                match &children["test.txt"] {
+                    FileNode::File { length, pieces_root: root } => {
+                        assert_eq!(*length, 10);
+                        assert_eq!(*root, Some(pieces_root));
+                    },
+                    _ => panic!("expected a file leaf"),
+                }
+            },
+            _ => panic!("expected a file tree dir"),
+        }
+
+        let layers = torrent.piece_layers.unwrap();
+        assert_eq!(layers.get(pieces_root.as_slice()), Some(&layer_hashes.to_vec()));
+    }
+
+    #[test]
+    fn test_truncated_pieces_string_errors_instead_of_panicking() {
+        let raw = format!(
+            "d8:announce4:http4:infod6:lengthi10e4:name4:test12:piece lengthi16384e6:pieces25:{}ee",
+            "x".repeat(25)
+        ).into_bytes();
+
+        let mut reader = std::io::Cursor::new(raw);
+        let mut parser = Parser::new(&mut reader);
+        let bencode = parser.parse().unwrap();
+        let err = TorrentFile::from_bencode(&bencode).unwrap_err();
+        assert_eq!(err, TorrentError::WrongType { key: "pieces", expected: "multiple of 20 bytes" });
+    }
+
+    #[test]
+    fn test_info_hash_v2_matches_known_fixture() {
+        let name = "test.txt";
+        let raw = format!(
+            "d8:announce4:http4:infod6:lengthi10e4:name{}:{}12:meta versioni2e12:piece lengthi16384eee",
+            name.len(), name
+        ).into_bytes();
+
+        let (torrent, raw) = parse_torrent(&raw);
+        let hash = torrent.info_hash_v2(&raw).unwrap();
+        let hex: String = hash.iter().map(|b| format!("{:02x}", b)).collect();
+        assert_eq!(hex, "b9a9b3bd73331c903e414ff0f02dfd87e2062df9b83ffe8ea88d9abc5581c48f");
+    }
+
+    #[test]
+    fn test_hybrid_torrent_exposes_both_v1_and_v2_info_hashes() {
+        let pieces = [0u8; 20];
+        let mut raw = format!(
+            "d8:announce4:http4:infod6:lengthi10e4:name4:test12:meta versioni2e12:piece lengthi16384e6:pieces{}:",
+            pieces.len()
+        ).into_bytes();
+        raw.extend_from_slice(&pieces);
+        raw.extend_from_slice(b"ee");
+
+        let (torrent, raw) = parse_torrent(&raw);
+
+        assert_eq!(torrent.info.meta_version, Some(2));
+        assert!(!torrent.info.pieces.0.is_empty());
+        assert!(torrent.info_hash_v2(&raw).is_some());
+        // A hybrid torrent carries v1 `pieces`/`length` alongside `meta
+        // version` 2, so the v1 info-hash must still be computable.
+        let v1_hash = torrent.info_hash(&raw);
+        assert_ne!(v1_hash, [0u8; 20]);
+    }
+
+    #[test]
+    fn test_missing_announce_field_errors() {
+        let raw = b"d4:infod6:lengthi10e4:name4:test12:piece lengthi16384eee";
+        let mut reader = std::io::Cursor::new(raw.to_vec());
+        let mut parser = Parser::new(&mut reader);
+        let bencode = parser.parse().unwrap();
+        let err = TorrentFile::from_bencode(&bencode).unwrap_err();
+        assert_eq!(err, TorrentError::MissingField("announce"));
+    }
+
+    #[test]
+    fn test_wrong_type_announce_field_errors() {
+        let raw = b"d8:announcei1e4:infod6:lengthi10e4:name4:test12:piece lengthi16384eee";
+        let mut reader = std::io::Cursor::new(raw.to_vec());
+        let mut parser = Parser::new(&mut reader);
+        let bencode = parser.parse().unwrap();
+        let err = TorrentFile::from_bencode(&bencode).unwrap_err();
+        assert_eq!(err, TorrentError::WrongType { key: "announce", expected: "string" });
+    }
+
+    #[test]
+    fn test_negative_file_length_errors_instead_of_overflowing() {
+        // A crafted multi-file torrent with a negative `length`: previously
+        // this cast to `u64` in `file_layout` and overflowed when summed
+        // against the other file's length in `verify()`.
+        let raw = b"d8:announce4:http4:infod5:filesld6:lengthi-1e4:pathl5:a.binee\
+                    d6:lengthi5e4:pathl5:b.bineee4:name4:test12:piece lengthi4e6:pieces0:ee";
+        let mut reader = std::io::Cursor::new(raw.to_vec());
+        let mut parser = Parser::new(&mut reader);
+        let bencode = parser.parse().unwrap();
+        let err = TorrentFile::from_bencode(&bencode).unwrap_err();
+        assert_eq!(err, TorrentError::WrongType { key: "length", expected: "non-negative integer" });
+    }
+
+    #[test]
+    fn test_magnet_link_includes_every_announce_list_tracker() {
+        let tracker_a = "http://a.test/";
+        let tracker_b = "http://b.test/";
+        let name = "My File";
+        let raw = format!(
+            "d8:announce{}:{}13:announce-listll{}:{}{}:{}ee4:infod4:name{}:{}12:piece lengthi16384eee",
+            tracker_a.len(), tracker_a,
+            tracker_a.len(), tracker_a, tracker_b.len(), tracker_b,
+            name.len(), name,
+        ).into_bytes();
+
+        let (torrent, raw) = parse_torrent(&raw);
+        let hash_hex: String = torrent.info_hash(&raw).iter().map(|b| format!("{:02x}", b)).collect();
+        let expected = format!(
+            "magnet:?xt=urn:btih:{}&dn=My%20File&tr=http%3A%2F%2Fa.test%2F&tr=http%3A%2F%2Fb.test%2F",
+            hash_hex
+        );
+        assert_eq!(torrent.magnet_link(&raw), expected);
+    }
+
+    #[test]
+    fn test_magnet_link_falls_back_to_btmh_for_pure_v2_torrents() {
+        let name = "test.txt";
+        let raw = format!(
+            "d8:announce4:http4:infod6:lengthi10e4:name{}:{}12:meta versioni2e12:piece lengthi16384eee",
+            name.len(), name
+        ).into_bytes();
+
+        let (torrent, raw) = parse_torrent(&raw);
+        assert!(torrent.info.pieces.0.is_empty());
+
+        let v2_hash = torrent.info_hash_v2(&raw).unwrap();
+        let mut multihash = vec![0x12u8, 0x20u8];
+        multihash.extend_from_slice(&v2_hash);
+        let hash_hex: String = multihash.iter().map(|b| format!("{:02x}", b)).collect();
+        let expected = format!("magnet:?xt=urn:btmh:{}&dn=test.txt&tr=http", hash_hex);
+        assert_eq!(torrent.magnet_link(&raw), expected);
+    }
+}